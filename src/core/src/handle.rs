@@ -0,0 +1,119 @@
+// Copyright 2015 The Gfx-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resource handles
+//!
+//! This module contains reference-counted handles to backend resources. A
+//! handle pairs the backend's own (opaque, `Resources`-associated) resource
+//! type with whatever frontend-side bookkeeping that resource needs; cloning
+//! a handle is cheap and only extends the resource's lifetime.
+
+use std::sync::Arc;
+use {pso, Resources};
+
+struct Inner<T, I> {
+    resource: T,
+    info: I,
+}
+
+/// A reference-counted handle pairing a raw backend resource with its info.
+pub struct Handle<T, I>(Arc<Inner<T, I>>);
+
+impl<T, I> Clone for Handle<T, I> {
+    fn clone(&self) -> Self {
+        Handle(self.0.clone())
+    }
+}
+
+impl<T: ::std::fmt::Debug, I: ::std::fmt::Debug> ::std::fmt::Debug for Handle<T, I> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.debug_struct("Handle")
+            .field("resource", &self.0.resource)
+            .field("info", &self.0.info)
+            .finish()
+    }
+}
+
+impl<T, I> Handle<T, I> {
+    /// Wrap a raw backend resource together with its info in a new handle.
+    pub fn new(resource: T, info: I) -> Handle<T, I> {
+        Handle(Arc::new(Inner { resource: resource, info: info }))
+    }
+    /// The raw backend resource.
+    pub fn resource(&self) -> &T {
+        &self.0.resource
+    }
+    /// The frontend-side info tracked alongside the resource.
+    pub fn info(&self) -> &I {
+        &self.0.info
+    }
+}
+
+/// Info tracked alongside a mapped buffer region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MappingInfo {
+    /// Byte offset of the mapped region within the buffer.
+    pub offset: usize,
+    /// Length of the mapped region, in bytes.
+    pub size: usize,
+}
+
+/// A handle to a region of a buffer that is currently mapped for CPU access.
+pub type RawMapping<R> = Handle<<R as Resources>::Mapping, MappingInfo>;
+
+/// Info tracked alongside a raw buffer resource.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BufferInfo {
+    mapping: Option<MappingInfo>,
+}
+
+/// A raw, backend-agnostic buffer handle.
+pub type RawBuffer<R> = Handle<<R as Resources>::Buffer, BufferInfo>;
+
+impl<R: Resources> Handle<R::Buffer, BufferInfo> {
+    /// Wrap a backend buffer together with the mapping it was created with,
+    /// if any.
+    pub fn new_buffer(resource: R::Buffer, mapping: Option<MappingInfo>) -> RawBuffer<R> {
+        Handle::new(resource, BufferInfo { mapping: mapping })
+    }
+    /// Returns the mapping covering this buffer, if it is currently mapped.
+    pub fn mapping(&self) -> Option<RawMapping<R>>
+        where R::Mapping: Clone
+    {
+        self.info().mapping.clone().map(|info| Handle::new(self.0.resource.clone(), info))
+    }
+}
+
+/// Info tracked alongside a raw query resource.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryInfo {
+    /// The kind of query this handle was created with.
+    pub kind: pso::QueryKind,
+}
+
+/// A raw, backend-agnostic handle to a GPU query object, returned by the
+/// factory and consumed by `pso::AccessInfo::query_used` and the command
+/// recorder's `pso::QueryRecorder::begin_query`/`end_query`.
+pub type RawQuery<R> = Handle<<R as Resources>::Query, QueryInfo>;
+
+impl<R: Resources> Handle<R::Query, QueryInfo> {
+    /// Wrap a backend query together with the kind it was created with.
+    pub fn new_query(resource: R::Query, kind: pso::QueryKind) -> RawQuery<R> {
+        Handle::new(resource, QueryInfo { kind: kind })
+    }
+    /// The kind of query this handle was created with.
+    pub fn kind(&self) -> pso::QueryKind {
+        self.info().kind
+    }
+}
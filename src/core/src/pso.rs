@@ -186,6 +186,11 @@ pub struct Descriptor {
     pub color_targets: [Option<ColorTargetDesc>; MAX_COLOR_TARGETS],
     /// Depth stencil view (DSV)
     pub depth_stencil: Option<DepthStencilDesc>,
+    /// Static multisample coverage mask, ANDed with the per-fragment coverage.
+    /// Only the low N bits are used, where N is the bound target's sample count.
+    pub sample_mask: u32,
+    /// Derive additional sample coverage from the fragment's output alpha.
+    pub alpha_to_coverage: bool,
 }
 
 impl Descriptor {
@@ -203,10 +208,268 @@ impl Descriptor {
             samplers: [None; MAX_SAMPLERS],
             color_targets: [None; MAX_COLOR_TARGETS],
             depth_stencil: None,
+            sample_mask: !0,
+            alpha_to_coverage: false,
+        }
+    }
+
+    /// The part of `sample_mask` that actually applies when the bound target
+    /// has `samples` subsamples: only the low `samples` bits are meaningful,
+    /// so this ANDs them off. Each backend's PSO creation should call this
+    /// (rather than using `sample_mask` verbatim) when it builds the native
+    /// multisample state; wiring that call into each backend is not part of
+    /// this core-only change, since no backend crate exists in this tree to
+    /// receive it.
+    pub fn sample_mask_for(&self, samples: u8) -> u32 {
+        if samples >= 32 {
+            self.sample_mask
+        } else {
+            self.sample_mask & ((1u32 << samples) - 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod descriptor_tests {
+    use super::*;
+    use state as s;
+
+    #[test]
+    fn sample_mask_for_keeps_only_low_n_bits() {
+        let mut desc = Descriptor::new(Primitive::TriangleList, s::Rasterizer::new_fill());
+        desc.sample_mask = 0xFFFF_FFFF;
+        assert_eq!(desc.sample_mask_for(4), 0xF);
+        assert_eq!(desc.sample_mask_for(1), 0x1);
+    }
+
+    #[test]
+    fn sample_mask_for_respects_a_partial_mask() {
+        let mut desc = Descriptor::new(Primitive::TriangleList, s::Rasterizer::new_fill());
+        desc.sample_mask = 0b1010;
+        assert_eq!(desc.sample_mask_for(4), 0b1010);
+        assert_eq!(desc.sample_mask_for(2), 0b10);
+    }
+
+    #[test]
+    fn sample_mask_for_32_samples_is_unmasked() {
+        let mut desc = Descriptor::new(Primitive::TriangleList, s::Rasterizer::new_fill());
+        desc.sample_mask = 0xABCD_1234;
+        assert_eq!(desc.sample_mask_for(32), 0xABCD_1234);
+    }
+}
+
+/// Error returned when a `DescriptorBuilder` slot array overflows its `MAX_*` bound.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BuildError {
+    /// More than `MAX_VERTEX_BUFFERS` vertex buffers were added.
+    VertexBuffers,
+    /// More than `MAX_VERTEX_ATTRIBUTES` attributes were added.
+    Attributes,
+    /// More than `MAX_COLOR_TARGETS` color targets were added.
+    ColorTargets,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+impl Error for BuildError {
+    fn description(&self) -> &str {
+        match *self {
+            BuildError::VertexBuffers => "Too many vertex buffers were added to the descriptor.",
+            BuildError::Attributes => "Too many attributes were added to the descriptor.",
+            BuildError::ColorTargets => "Too many color targets were added to the descriptor.",
         }
     }
 }
 
+/// A fluent builder for `Descriptor` with sensible defaults, auto-assigning
+/// each added vertex buffer, attribute, and color target to the next free slot.
+#[derive(Clone, Debug)]
+pub struct DescriptorBuilder {
+    descriptor: Descriptor,
+    num_vertex_buffers: usize,
+    num_attributes: usize,
+    num_color_targets: usize,
+    error: Option<BuildError>,
+}
+
+impl DescriptorBuilder {
+    /// Default primitive topology.
+    pub const DEFAULT_PRIMITIVE: Primitive = Primitive::TriangleList;
+    /// Default rasterizer: filled, no culling, counter-clockwise front face.
+    pub const DEFAULT_RASTERIZER: s::Rasterizer = s::Rasterizer {
+        front_face: s::FrontFace::CounterClockwise,
+        cull_face: s::CullFace::Nothing,
+        method: s::RasterMethod::Fill,
+        offset: None,
+    };
+    /// Default color target state: full color mask, no blending (replace).
+    pub const DEFAULT_COLOR_BLEND: ColorInfo = ColorInfo {
+        mask: s::MASK_ALL,
+        color: None,
+        alpha: None,
+    };
+    /// Default depth-stencil state: depth testing on, `LessEqual`, writes enabled.
+    pub const DEFAULT_DEPTH_STENCIL: DepthStencilInfo = DepthStencilInfo {
+        depth: Some(s::Depth { fun: s::Comparison::LessEqual, write: true }),
+        front: None,
+        back: None,
+    };
+
+    /// Start building a new `Descriptor` with `DEFAULT_PRIMITIVE` and
+    /// `DEFAULT_RASTERIZER`. No color or depth-stencil target is set, so
+    /// `DEFAULT_COLOR_BLEND` and `DEFAULT_DEPTH_STENCIL` apply only when
+    /// passed to `add_color_target`/`depth_stencil`.
+    pub fn new() -> DescriptorBuilder {
+        DescriptorBuilder {
+            descriptor: Descriptor::new(Self::DEFAULT_PRIMITIVE, Self::DEFAULT_RASTERIZER),
+            num_vertex_buffers: 0,
+            num_attributes: 0,
+            num_color_targets: 0,
+            error: None,
+        }
+    }
+
+    /// Add a vertex buffer to the next free slot.
+    pub fn add_vertex_buffer(mut self, desc: VertexBufferDesc) -> DescriptorBuilder {
+        if self.num_vertex_buffers >= MAX_VERTEX_BUFFERS {
+            self.error = self.error.or(Some(BuildError::VertexBuffers));
+            return self;
+        }
+        self.descriptor.vertex_buffers[self.num_vertex_buffers] = Some(desc);
+        self.num_vertex_buffers += 1;
+        self
+    }
+
+    /// Add a vertex attribute to the next free slot.
+    pub fn add_attribute(mut self, buffer: BufferIndex, element: Element<format::Format>) -> DescriptorBuilder {
+        if self.num_attributes >= MAX_VERTEX_ATTRIBUTES {
+            self.error = self.error.or(Some(BuildError::Attributes));
+            return self;
+        }
+        self.descriptor.attributes[self.num_attributes] = Some((buffer, element));
+        self.num_attributes += 1;
+        self
+    }
+
+    /// Add a color target to the next free slot.
+    pub fn add_color_target(mut self, format: format::Format, info: ColorInfo) -> DescriptorBuilder {
+        if self.num_color_targets >= MAX_COLOR_TARGETS {
+            self.error = self.error.or(Some(BuildError::ColorTargets));
+            return self;
+        }
+        self.descriptor.color_targets[self.num_color_targets] = Some((format, info));
+        self.num_color_targets += 1;
+        self
+    }
+
+    /// Set the depth-stencil target.
+    pub fn depth_stencil(mut self, format: format::Format, info: DepthStencilInfo) -> DescriptorBuilder {
+        self.descriptor.depth_stencil = Some((format, info));
+        self
+    }
+
+    /// Finish building and return the resulting `Descriptor`, or the first
+    /// slot overflow encountered while chaining.
+    pub fn build(self) -> Result<Descriptor, BuildError> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.descriptor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use super::*;
+    use format::{ChannelType, Format, SurfaceType};
+
+    fn rgba8() -> Format {
+        Format(SurfaceType::R8_G8_B8_A8, ChannelType::Unorm)
+    }
+
+    #[test]
+    fn vertex_buffers_fill_slots_in_order() {
+        let mut builder = DescriptorBuilder::new();
+        for i in 0 .. MAX_VERTEX_BUFFERS {
+            builder = builder.add_vertex_buffer(VertexBufferDesc { stride: i as ElemStride, rate: 0 });
+        }
+        let desc = builder.build().unwrap();
+        for i in 0 .. MAX_VERTEX_BUFFERS {
+            assert_eq!(desc.vertex_buffers[i], Some(VertexBufferDesc { stride: i as ElemStride, rate: 0 }));
+        }
+    }
+
+    #[test]
+    fn vertex_buffers_overflow_errors() {
+        let mut builder = DescriptorBuilder::new();
+        for _ in 0 .. MAX_VERTEX_BUFFERS {
+            builder = builder.add_vertex_buffer(VertexBufferDesc { stride: 0, rate: 0 });
+        }
+        builder = builder.add_vertex_buffer(VertexBufferDesc { stride: 0, rate: 0 });
+        assert_eq!(builder.build(), Err(BuildError::VertexBuffers));
+    }
+
+    #[test]
+    fn attributes_fill_slots_in_order() {
+        let mut builder = DescriptorBuilder::new();
+        for i in 0 .. MAX_VERTEX_ATTRIBUTES {
+            builder = builder.add_attribute(0, Element { format: rgba8(), offset: i as ElemOffset });
+        }
+        let desc = builder.build().unwrap();
+        for i in 0 .. MAX_VERTEX_ATTRIBUTES {
+            assert_eq!(desc.attributes[i], Some((0, Element { format: rgba8(), offset: i as ElemOffset })));
+        }
+    }
+
+    #[test]
+    fn attributes_overflow_errors() {
+        let mut builder = DescriptorBuilder::new();
+        for _ in 0 .. MAX_VERTEX_ATTRIBUTES {
+            builder = builder.add_attribute(0, Element { format: rgba8(), offset: 0 });
+        }
+        builder = builder.add_attribute(0, Element { format: rgba8(), offset: 0 });
+        assert_eq!(builder.build(), Err(BuildError::Attributes));
+    }
+
+    #[test]
+    fn color_targets_fill_slots_in_order() {
+        let mut builder = DescriptorBuilder::new();
+        for _ in 0 .. MAX_COLOR_TARGETS {
+            builder = builder.add_color_target(rgba8(), DescriptorBuilder::DEFAULT_COLOR_BLEND);
+        }
+        let desc = builder.build().unwrap();
+        for i in 0 .. MAX_COLOR_TARGETS {
+            assert_eq!(desc.color_targets[i], Some((rgba8(), DescriptorBuilder::DEFAULT_COLOR_BLEND)));
+        }
+    }
+
+    #[test]
+    fn color_targets_overflow_errors() {
+        let mut builder = DescriptorBuilder::new();
+        for _ in 0 .. MAX_COLOR_TARGETS {
+            builder = builder.add_color_target(rgba8(), DescriptorBuilder::DEFAULT_COLOR_BLEND);
+        }
+        builder = builder.add_color_target(rgba8(), DescriptorBuilder::DEFAULT_COLOR_BLEND);
+        assert_eq!(builder.build(), Err(BuildError::ColorTargets));
+    }
+
+    #[test]
+    fn first_overflow_wins_on_build() {
+        let mut builder = DescriptorBuilder::new();
+        for _ in 0 .. MAX_VERTEX_BUFFERS + 1 {
+            builder = builder.add_vertex_buffer(VertexBufferDesc { stride: 0, rate: 0 });
+        }
+        for _ in 0 .. MAX_VERTEX_ATTRIBUTES + 1 {
+            builder = builder.add_attribute(0, Element { format: rgba8(), offset: 0 });
+        }
+        assert_eq!(builder.build(), Err(BuildError::VertexBuffers));
+    }
+}
+
 /// A complete set of vertex buffers to be used for vertex import in PSO.
 #[derive(Copy, Clone, Debug)]
 pub struct VertexBufferSet<R: Resources>(
@@ -237,6 +500,30 @@ pub struct UnorderedViewParam<R: Resources>(pub R::UnorderedAccessView, pub Usag
 #[derive(Copy, Clone, Debug)]
 pub struct SamplerParam<R: Resources>(pub R::Sampler, pub Usage, pub SamplerSlot);
 
+/// Dynamic stencil reference value and test masks, supplied at draw time
+/// through a `RawDataSet`'s `RefValues` rather than baked into the PSO.
+/// `DepthStencilInfo::front`/`back` still carry `s::StencilSide`'s own
+/// `read_mask`/`write_mask`, used as the PSO's defaults at creation time;
+/// a `StencilParam` overrides the reference value and masks per draw
+/// without rebuilding the PSO, so techniques like outlines, portals, and
+/// decals that vary the reference value frequently don't cause a
+/// combinatorial PSO explosion.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StencilParam {
+    /// Reference value for the front-facing stencil test.
+    pub front_ref: s::StencilValue,
+    /// Reference value for the back-facing stencil test.
+    pub back_ref: s::StencilValue,
+    /// Bits of the front-facing stencil buffer considered by the test.
+    pub front_read: s::StencilValue,
+    /// Bits of the back-facing stencil buffer considered by the test.
+    pub back_read: s::StencilValue,
+    /// Bits of the front-facing stencil buffer updated by the test.
+    pub front_write: s::StencilValue,
+    /// Bits of the back-facing stencil buffer updated by the test.
+    pub back_write: s::StencilValue,
+}
+
 /// A complete set of render targets to be used for pixel export in PSO.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct PixelTargetSet<R: Resources> {
@@ -282,11 +569,120 @@ impl<R: Resources> PixelTargetSet<R> {
     }
 }
 
+/// Non-resource values supplied at draw time that a PSO's descriptor leaves
+/// dynamic rather than baking in.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RefValues {
+    /// Stencil reference value and test masks for this draw.
+    pub stencil: StencilParam,
+}
+
+/// A complete set of run-time parameters matching a `Descriptor`'s slots,
+/// bound together for a single draw call.
+#[derive(Clone, Debug)]
+pub struct RawDataSet<R: Resources> {
+    /// Vertex buffers bound to the vertex buffer slots.
+    pub vertex_buffers: VertexBufferSet<R>,
+    /// Constant buffers bound to the constant buffer slots.
+    pub constant_buffers: Vec<ConstantBufferParam<R>>,
+    /// Shader resource views bound to the resource view slots.
+    pub resource_views: Vec<ResourceViewParam<R>>,
+    /// Unordered access views bound to the unordered view slots.
+    pub unordered_views: Vec<UnorderedViewParam<R>>,
+    /// Samplers bound to the sampler slots.
+    pub samplers: Vec<SamplerParam<R>>,
+    /// Color and depth/stencil targets bound for pixel export.
+    pub pixel_targets: PixelTargetSet<R>,
+    /// Non-resource values left dynamic by the PSO's descriptor.
+    pub ref_values: RefValues,
+}
+
+impl<R: Resources> RawDataSet<R> {
+    /// Create an empty data set with no buffers, views, or targets bound.
+    pub fn new() -> RawDataSet<R> {
+        RawDataSet {
+            vertex_buffers: VertexBufferSet::new(),
+            constant_buffers: Vec::new(),
+            resource_views: Vec::new(),
+            unordered_views: Vec::new(),
+            samplers: Vec::new(),
+            pixel_targets: PixelTargetSet::new(),
+            ref_values: RefValues::default(),
+        }
+    }
+}
+
+/// The kind of a GPU query.
+///
+/// The query handle itself is `handle::RawQuery<R>`, created by the factory
+/// and carrying its `QueryKind` alongside the backend's own query resource.
+/// Scoping a query around the draws it should cover is done through the
+/// `QueryRecorder` trait below, which the command recorder implements.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum QueryKind {
+    /// Counts the samples that pass the depth/stencil test between the
+    /// query's begin and end scope.
+    Occlusion,
+    /// Counts the pipeline stage invocations selected by `StatisticsFlags`
+    /// between the query's begin and end scope.
+    PipelineStatistics(StatisticsFlags),
+    /// Records a single GPU timestamp.
+    Timestamp,
+}
+
+/// Bitmask selecting which counters a `QueryKind::PipelineStatistics` query
+/// gathers; results are reported in a `PipelineStatistics` value.
+pub type StatisticsFlags = u16;
+/// Number of vertices submitted.
+pub const STATS_VERTICES: StatisticsFlags = 0x001;
+/// Number of primitives submitted.
+pub const STATS_PRIMITIVES: StatisticsFlags = 0x002;
+/// Number of vertex shader invocations.
+pub const STATS_VERTEX_SHADER_INVOCATIONS: StatisticsFlags = 0x004;
+/// Number of geometry shader invocations.
+pub const STATS_GEOMETRY_SHADER_INVOCATIONS: StatisticsFlags = 0x008;
+/// Number of primitives output by the geometry shader.
+pub const STATS_GEOMETRY_SHADER_PRIMITIVES: StatisticsFlags = 0x010;
+/// Number of primitives that entered primitive clipping.
+pub const STATS_CLIPPING_INVOCATIONS: StatisticsFlags = 0x020;
+/// Number of primitives that passed primitive clipping.
+pub const STATS_CLIPPING_PRIMITIVES: StatisticsFlags = 0x040;
+/// Number of fragment shader invocations.
+pub const STATS_FRAGMENT_SHADER_INVOCATIONS: StatisticsFlags = 0x080;
+/// Number of compute shader invocations.
+pub const STATS_COMPUTE_SHADER_INVOCATIONS: StatisticsFlags = 0x100;
+/// All counters.
+pub const STATS_ALL: StatisticsFlags = 0x1FF;
+
+/// Pipeline statistics counters, as gathered by a `QueryKind::PipelineStatistics` query.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PipelineStatistics {
+    /// Number of vertices submitted.
+    pub vertices: u64,
+    /// Number of primitives submitted.
+    pub primitives: u64,
+    /// Number of vertex shader invocations.
+    pub vertex_shader_invocations: u64,
+    /// Number of geometry shader invocations.
+    pub geometry_shader_invocations: u64,
+    /// Number of primitives output by the geometry shader.
+    pub geometry_shader_primitives: u64,
+    /// Number of primitives that entered primitive clipping.
+    pub clipping_invocations: u64,
+    /// Number of primitives that passed primitive clipping.
+    pub clipping_primitives: u64,
+    /// Number of fragment shader invocations.
+    pub fragment_shader_invocations: u64,
+    /// Number of compute shader invocations.
+    pub compute_shader_invocations: u64,
+}
+
 /// Informations about what is accessed by the pipeline
 #[derive(Debug)]
 pub struct AccessInfo<R: Resources> {
     mapped_reads: Vec<handle::RawMapping<R>>,
     mapped_writes: Vec<handle::RawMapping<R>>,
+    queries_used: Vec<handle::RawQuery<R>>,
 }
 
 impl<R: Resources> AccessInfo<R> {
@@ -295,6 +691,7 @@ impl<R: Resources> AccessInfo<R> {
         AccessInfo {
             mapped_reads: Vec::new(),
             mapped_writes: Vec::new(),
+            queries_used: Vec::new(),
         }
     }
 
@@ -302,6 +699,7 @@ impl<R: Resources> AccessInfo<R> {
     pub fn clear(&mut self) {
         self.mapped_reads.clear();
         self.mapped_writes.clear();
+        self.queries_used.clear();
     }
 
     /// Register a buffer read access
@@ -318,6 +716,12 @@ impl<R: Resources> AccessInfo<R> {
         }
     }
 
+    /// Register a query as used by this submission; its result must be
+    /// resolved or fenced before readback.
+    pub fn query_used(&mut self, query: &handle::RawQuery<R>) {
+        self.queries_used.push(query.clone());
+    }
+
     /// Returns a slice of mappings associated to buffers that The GPU will read from
     pub fn mapped_reads(&self) -> &[handle::RawMapping<R>] {
         &self.mapped_reads[..]
@@ -327,5 +731,239 @@ impl<R: Resources> AccessInfo<R> {
     pub fn mapped_writes(&self) -> &[handle::RawMapping<R>] {
         &self.mapped_writes[..]
     }
+
+    /// Returns a slice of the queries used by this submission, whose results
+    /// must be resolved/fenced before readback.
+    pub fn queries_used(&self) -> &[handle::RawQuery<R>] {
+        &self.queries_used[..]
+    }
+}
+
+/// Scopes a query around the draws it should cover.
+///
+/// The command recorder implements this to wrap `begin_query`/`end_query`
+/// around whatever draws fall inside the query's scope; a query's result is
+/// only well-defined once a matching `end_query` has been recorded for the
+/// `begin_query` that opened it.
+pub trait QueryRecorder<R: Resources> {
+    /// Starts counting/timestamping for `query`.
+    fn begin_query(&mut self, query: &handle::RawQuery<R>);
+    /// Stops counting/timestamping for `query`, finalizing its result.
+    fn end_query(&mut self, query: &handle::RawQuery<R>);
+}
+
+/// Built-in surface-to-surface blit helper.
+///
+/// Performs format-converting and resolve-capable copies between
+/// `PixelTargetSet`s by drawing a full-screen quad, covering cases the
+/// device's `copy` command cannot handle: differing formats, scaling, MSAA
+/// resolve, and depth/stencil. This mirrors the classic `u_blit` design: a
+/// small set of PSO descriptors is lazily built and cached, keyed by the
+/// destination format (and, for color, by write mask; for stencil, by
+/// bitplane), and `Blitter::blit_color`/`blit_depth`/`blit_stencil` take
+/// source/destination `PixelTargetSet`s and rectangles and issue the draws.
+/// The cached descriptors hold only fixed-function state; this module
+/// provides no shaders. The shader program (sampling the source, writing
+/// depth, exporting stencil or discarding by source bit), binding the
+/// sampler SRV and submitting the quad draw are all left to the `Draw`
+/// implementation the caller supplies.
+pub mod blit {
+    use std::collections::HashMap;
+    use format;
+    use state as s;
+    use Resources;
+    use super::{ColorInfo, DepthStencilInfo, Descriptor, DescriptorBuilder, PixelTargetSet};
+
+    /// Number of masked per-bitplane passes used to blit stencil when the
+    /// hardware does not support shader stencil export.
+    pub const STENCIL_BITPLANES: u8 = 8;
+
+    /// An axis-aligned rectangle, in pixels, with the origin at the top-left.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct Rect {
+        /// Left edge.
+        pub x: u16,
+        /// Top edge.
+        pub y: u16,
+        /// Width.
+        pub w: u16,
+        /// Height.
+        pub h: u16,
+    }
+
+    /// What `Blitter` needs from its caller to actually perform a blit: bind
+    /// `pso`, sample `src` within `src_rect`, and draw a full-screen quad
+    /// into `dst` within `dst_rect`. Implemented by the command
+    /// recorder/encoder in the higher-level `gfx` crate, which has the
+    /// device access this raw PSO module does not.
+    pub trait Draw<R: Resources> {
+        /// Issue one full-screen-quad draw using `pso`.
+        fn draw_quad(&mut self, pso: &Descriptor,
+                     src: &PixelTargetSet<R>, src_rect: Rect,
+                     dst: &PixelTargetSet<R>, dst_rect: Rect);
+    }
+
+    /// Identifies one of the cached blit PSO variants.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    enum Key {
+        /// Format-converting color copy with the given write mask.
+        Color(format::Format, s::ColorMask),
+        /// Depth-only copy.
+        Depth(format::Format),
+        /// Stencil copy via shader stencil export.
+        StencilExport(format::Format),
+        /// One masked pass of a per-bitplane stencil copy.
+        StencilBitplane(format::Format, u8),
+    }
+
+    /// Lazily builds and caches the `Descriptor`s needed to blit between
+    /// render targets by drawing a full-screen quad. Does not touch the
+    /// device itself; callers turn the returned descriptors into PSOs and
+    /// issue the actual draws.
+    pub struct Blitter {
+        descriptors: HashMap<Key, Descriptor>,
+        /// Whether the backend supports shader stencil export; if not,
+        /// stencil blits fall back to `STENCIL_BITPLANES` masked passes.
+        stencil_export: bool,
+    }
+
+    impl Blitter {
+        /// Create a new, empty blitter.
+        pub fn new(stencil_export: bool) -> Blitter {
+            Blitter {
+                descriptors: HashMap::new(),
+                stencil_export: stencil_export,
+            }
+        }
+
+        /// Get (building and caching if necessary) the PSO descriptor used
+        /// to blit a color target of the given destination format, writing
+        /// only the channels in `mask`.
+        pub fn color_descriptor(&mut self, format: format::Format, mask: s::ColorMask) -> &Descriptor {
+            self.descriptors.entry(Key::Color(format, mask)).or_insert_with(|| {
+                DescriptorBuilder::new()
+                    .add_color_target(format, ColorInfo { mask: mask, color: None, alpha: None })
+                    .build()
+                    .expect("blit color descriptor has a single color target")
+            })
+        }
+
+        /// Get (building and caching if necessary) the PSO descriptor used
+        /// to blit a depth target of the given destination format: depth
+        /// test `Always` with writes on. The fragment shader that writes the
+        /// sampled depth comes from the caller.
+        pub fn depth_descriptor(&mut self, format: format::Format) -> &Descriptor {
+            self.descriptors.entry(Key::Depth(format)).or_insert_with(|| {
+                let depth_stencil = DepthStencilInfo {
+                    depth: Some(s::Depth { fun: s::Comparison::Always, write: true }),
+                    front: None,
+                    back: None,
+                };
+                DescriptorBuilder::new()
+                    .depth_stencil(format, depth_stencil)
+                    .build()
+                    .expect("blit depth descriptor has a single depth-stencil target")
+            })
+        }
+
+        /// Get (building and caching if necessary) the PSO descriptor(s)
+        /// used to blit a stencil target of the given destination format.
+        /// When shader stencil export is available this is a single pass;
+        /// otherwise it is `STENCIL_BITPLANES` passes, one per bit, each with
+        /// `write_mask` limited to that bit. Discarding fragments whose
+        /// source bit is not set is up to the caller's fragment shader.
+        pub fn stencil_descriptors(&mut self, format: format::Format) -> Vec<&Descriptor> {
+            if self.stencil_export {
+                self.descriptors.entry(Key::StencilExport(format)).or_insert_with(|| {
+                    let side = s::StencilSide {
+                        fun: s::Comparison::Always,
+                        op_fail: s::StencilOp::Replace,
+                        op_depth_fail: s::StencilOp::Replace,
+                        op_pass: s::StencilOp::Replace,
+                        read_mask: !0,
+                        write_mask: !0,
+                    };
+                    let depth_stencil = DepthStencilInfo {
+                        depth: None,
+                        front: Some(side),
+                        back: Some(side),
+                    };
+                    DescriptorBuilder::new()
+                        .depth_stencil(format, depth_stencil)
+                        .build()
+                        .expect("blit stencil export descriptor has a single depth-stencil target")
+                });
+                vec![&self.descriptors[&Key::StencilExport(format)]]
+            } else {
+                for bit in 0 .. STENCIL_BITPLANES {
+                    self.descriptors.entry(Key::StencilBitplane(format, bit)).or_insert_with(|| {
+                        let side = s::StencilSide {
+                            fun: s::Comparison::Always,
+                            op_fail: s::StencilOp::Keep,
+                            op_depth_fail: s::StencilOp::Keep,
+                            op_pass: s::StencilOp::Replace,
+                            read_mask: !0,
+                            write_mask: 1 << bit,
+                        };
+                        let depth_stencil = DepthStencilInfo {
+                            depth: None,
+                            front: Some(side),
+                            back: Some(side),
+                        };
+                        DescriptorBuilder::new()
+                            .depth_stencil(format, depth_stencil)
+                            .build()
+                            .expect("blit stencil bitplane descriptor has a single depth-stencil target")
+                    });
+                }
+                (0 .. STENCIL_BITPLANES)
+                    .map(|bit| &self.descriptors[&Key::StencilBitplane(format, bit)])
+                    .collect()
+            }
+        }
+
+        /// Blit the color contents of `src_rect` in `src` onto `dst_rect` in
+        /// `dst`, converting formats and resolving MSAA as needed, writing
+        /// only the channels in `mask`. Builds (and caches) the PSO, then
+        /// hands it to `draw` to actually sample and draw the quad.
+        pub fn blit_color<R, D>(&mut self, draw: &mut D,
+                                 src: &PixelTargetSet<R>, src_rect: Rect,
+                                 dst: &PixelTargetSet<R>, dst_rect: Rect,
+                                 format: format::Format, mask: s::ColorMask)
+            where R: Resources, D: Draw<R>
+        {
+            let pso = self.color_descriptor(format, mask).clone();
+            draw.draw_quad(&pso, src, src_rect, dst, dst_rect);
+        }
+
+        /// Blit the depth contents of `src_rect` in `src` onto `dst_rect` in
+        /// `dst`. Builds (and caches) the PSO, then hands it to `draw` to
+        /// actually sample and draw the quad.
+        pub fn blit_depth<R, D>(&mut self, draw: &mut D,
+                                 src: &PixelTargetSet<R>, src_rect: Rect,
+                                 dst: &PixelTargetSet<R>, dst_rect: Rect,
+                                 format: format::Format)
+            where R: Resources, D: Draw<R>
+        {
+            let pso = self.depth_descriptor(format).clone();
+            draw.draw_quad(&pso, src, src_rect, dst, dst_rect);
+        }
+
+        /// Blit the stencil contents of `src_rect` in `src` onto `dst_rect`
+        /// in `dst`. Builds (and caches) the PSO(s), then hands each to
+        /// `draw` in turn: a single pass when shader stencil export is
+        /// available, or `STENCIL_BITPLANES` masked passes otherwise.
+        pub fn blit_stencil<R, D>(&mut self, draw: &mut D,
+                                   src: &PixelTargetSet<R>, src_rect: Rect,
+                                   dst: &PixelTargetSet<R>, dst_rect: Rect,
+                                   format: format::Format)
+            where R: Resources, D: Draw<R>
+        {
+            let psos: Vec<Descriptor> = self.stencil_descriptors(format).into_iter().cloned().collect();
+            for pso in &psos {
+                draw.draw_quad(pso, src, src_rect, dst, dst_rect);
+            }
+        }
+    }
 }
 
@@ -0,0 +1,237 @@
+// Copyright 2015 The Gfx-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixed-function pipeline state
+//!
+//! This module describes the parts of the pipeline that aren't programmable:
+//! rasterization, color blending, and depth/stencil testing. `pso::Descriptor`
+//! bakes these in at PSO creation time.
+
+/// Winding order of vertices that determines the "front" face of a triangle.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FrontFace {
+    /// Clockwise winding is the front face.
+    Clockwise,
+    /// Counter-clockwise winding is the front face.
+    CounterClockwise,
+}
+
+/// Which face(s) of a triangle to discard during rasterization.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CullFace {
+    /// Discard nothing.
+    Nothing,
+    /// Discard front-facing triangles.
+    Front,
+    /// Discard back-facing triangles.
+    Back,
+}
+
+/// How to rasterize a triangle's interior.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RasterMethod {
+    /// Draw only the outline of the triangle.
+    Line(i32),
+    /// Fill the whole triangle.
+    Fill,
+}
+
+/// Depth bias and slope-scaled depth bias applied to rasterized fragments.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Offset(pub i32, pub i32);
+
+/// Rasterizer configuration of the PSO.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rasterizer {
+    /// Winding order that determines the front face.
+    pub front_face: FrontFace,
+    /// Which face(s) to cull.
+    pub cull_face: CullFace,
+    /// How to fill rasterized triangles.
+    pub method: RasterMethod,
+    /// Optional depth bias/offset.
+    pub offset: Option<Offset>,
+}
+
+impl Rasterizer {
+    /// A rasterizer that fills triangles without culling or depth bias.
+    pub fn new_fill() -> Rasterizer {
+        Rasterizer {
+            front_face: FrontFace::CounterClockwise,
+            cull_face: CullFace::Nothing,
+            method: RasterMethod::Fill,
+            offset: None,
+        }
+    }
+}
+
+/// A mask selecting which color channels are written.
+pub type ColorMask = u8;
+/// Write the red channel.
+pub const MASK_RED: ColorMask = 0x1;
+/// Write the green channel.
+pub const MASK_GREEN: ColorMask = 0x2;
+/// Write the blue channel.
+pub const MASK_BLUE: ColorMask = 0x4;
+/// Write the alpha channel.
+pub const MASK_ALPHA: ColorMask = 0x8;
+/// Write all channels.
+pub const MASK_ALL: ColorMask = 0xF;
+
+/// A factor modifying a color or alpha value in a blend equation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlendValue {
+    /// The incoming (source) value.
+    SourceColor,
+    /// The incoming (source) alpha.
+    SourceAlpha,
+    /// The value already in the target.
+    DestColor,
+    /// The alpha already in the target.
+    DestAlpha,
+    /// A constant blend color.
+    ConstColor,
+    /// A constant blend alpha.
+    ConstAlpha,
+}
+
+/// Source and destination factors for one blend equation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Factor {
+    /// Multiply by zero.
+    Zero,
+    /// Multiply by one.
+    One,
+    /// Multiply by the source alpha, saturated.
+    SourceAlphaSaturated,
+    /// Multiply by the given value.
+    Value(BlendValue),
+    /// Multiply by one minus the given value.
+    OneMinusValue(BlendValue),
+}
+
+/// How source and destination factors are combined.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Equation {
+    /// `src + dst`
+    Add,
+    /// `src - dst`
+    Sub,
+    /// `dst - src`
+    RevSub,
+    /// `min(src, dst)`
+    Min,
+    /// `max(src, dst)`
+    Max,
+}
+
+/// Blending equation and factors for one channel (color or alpha).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlendChannel {
+    /// How the source and destination are combined.
+    pub equation: Equation,
+    /// Factor applied to the source value.
+    pub source: Factor,
+    /// Factor applied to the destination value.
+    pub destination: Factor,
+}
+
+/// Full blending configuration, covering both color and alpha.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Blend {
+    /// Blending of the color channels.
+    pub color: BlendChannel,
+    /// Blending of the alpha channel.
+    pub alpha: BlendChannel,
+}
+
+/// A comparison function used by depth and stencil tests.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Comparison {
+    /// Never passes.
+    Never,
+    /// Passes if the new value is less than the existing one.
+    Less,
+    /// Passes if the values are equal.
+    Equal,
+    /// Passes if the new value is less than or equal to the existing one.
+    LessEqual,
+    /// Passes if the new value is greater than the existing one.
+    Greater,
+    /// Passes if the values are not equal.
+    NotEqual,
+    /// Passes if the new value is greater than or equal to the existing one.
+    GreaterEqual,
+    /// Always passes.
+    Always,
+}
+
+/// Depth test configuration.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Depth {
+    /// Function used to compare the new depth value against the existing one.
+    pub fun: Comparison,
+    /// Whether passing fragments write their depth value.
+    pub write: bool,
+}
+
+/// What to do to a stencil value when a test passes or fails.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StencilOp {
+    /// Keep the current value.
+    Keep,
+    /// Set the value to zero.
+    Zero,
+    /// Replace the value with the reference value.
+    Replace,
+    /// Increment the value, clamping at the maximum.
+    IncrementClamp,
+    /// Increment the value, wrapping to zero on overflow.
+    IncrementWrap,
+    /// Decrement the value, clamping at zero.
+    DecrementClamp,
+    /// Decrement the value, wrapping on underflow.
+    DecrementWrap,
+    /// Bitwise-invert the value.
+    Invert,
+}
+
+/// A stencil reference value or bit mask.
+pub type StencilValue = u8;
+
+/// Stencil test configuration for one face (front or back).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct StencilSide {
+    /// Comparison function used by the stencil test.
+    pub fun: Comparison,
+    /// Bits of the stencil buffer considered by the test.
+    pub read_mask: StencilValue,
+    /// Bits of the stencil buffer updated by the test.
+    pub write_mask: StencilValue,
+    /// Operation to apply when the stencil test fails.
+    pub op_fail: StencilOp,
+    /// Operation to apply when the stencil test passes but the depth test fails.
+    pub op_depth_fail: StencilOp,
+    /// Operation to apply when both the stencil and depth tests pass.
+    pub op_pass: StencilOp,
+}
+
+/// Stencil test configuration, covering both faces.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Stencil {
+    /// Stencil test for front-facing triangles.
+    pub front: StencilSide,
+    /// Stencil test for back-facing triangles.
+    pub back: StencilSide,
+}